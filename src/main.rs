@@ -4,6 +4,7 @@ use log::{error, info};
 use metrics::{counter, gauge};
 use metrics_exporter_prometheus::PrometheusBuilder;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     instruction::Instruction,
@@ -21,6 +22,7 @@ use std::{
 use tokio::time::sleep;
 
 mod error;
+mod tpu;
 mod transaction_service;
 
 use error::TransactionError;
@@ -48,6 +50,34 @@ struct Args {
     /// Metrics port for Prometheus
     #[clap(long, default_value = "9000")]
     metrics_port: u16,
+
+    /// Forward transactions directly to slot leaders over TPU instead of RPC
+    #[clap(long)]
+    use_tpu: bool,
+
+    /// Number of upcoming slots whose leaders each TPU transaction is fanned out to
+    #[clap(long, default_value = "4")]
+    tpu_fanout: u64,
+
+    /// Skip preflight simulation on the RPC node when sending transactions
+    #[clap(long)]
+    skip_preflight: bool,
+
+    /// Commitment used for preflight simulation (processed/confirmed/finalized)
+    #[clap(long)]
+    preflight_commitment: Option<String>,
+
+    /// Server-side rebroadcast retries performed by the RPC node
+    #[clap(long)]
+    server_max_retries: Option<usize>,
+
+    /// Minimum context slot the RPC node must have reached before sending
+    #[clap(long)]
+    min_context_slot: Option<u64>,
+
+    /// Submit all sample transactions as one concurrent batch and report TPS
+    #[clap(long)]
+    batch: bool,
 }
 
 #[tokio::main]
@@ -67,8 +97,10 @@ async fn main() -> Result<()> {
     info!("Metrics server running on port {}", args.metrics_port);
 
     // Initialize RPC client
-    let rpc_client =
-        RpcClient::new_with_commitment(args.rpc_url.clone(), CommitmentConfig::confirmed());
+    let rpc_client = Arc::new(RpcClient::new_with_commitment(
+        args.rpc_url.clone(),
+        CommitmentConfig::confirmed(),
+    ));
 
     // Load or generate keypair
     let payer = match args.keypair_path {
@@ -85,10 +117,30 @@ async fn main() -> Result<()> {
     info!("Using address: {}", payer.pubkey());
 
     // Create transaction service
-    let transaction_service = Arc::new(TransactionService::new(
-        Arc::new(rpc_client),
-        args.max_retries,
-    ));
+    let mut transaction_service =
+        TransactionService::new(rpc_client.clone(), args.max_retries);
+
+    // Build the RPC send configuration from the client-controlled flags.
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: args.skip_preflight,
+        preflight_commitment: args
+            .preflight_commitment
+            .as_deref()
+            .map(|level| CommitmentConfig::from_str(level).map(|c| c.commitment))
+            .transpose()
+            .map_err(|err| anyhow::anyhow!("invalid preflight commitment: {}", err))?,
+        max_retries: args.server_max_retries,
+        min_context_slot: args.min_context_slot,
+        ..RpcSendTransactionConfig::default()
+    };
+    transaction_service.set_send_config(send_config);
+
+    if args.use_tpu {
+        info!("Enabling direct TPU send path (fanout {})", args.tpu_fanout);
+        transaction_service.enable_tpu(rpc_client.clone(), args.tpu_fanout)?;
+    }
+
+    let transaction_service = Arc::new(transaction_service);
 
     // Display wallet balance
     let balance = transaction_service.get_balance(&payer.pubkey()).await?;
@@ -105,6 +157,40 @@ async fn main() -> Result<()> {
     counter!("solana.transactions.success", 0);
     counter!("solana.transactions.failed", 0);
 
+    // Batch mode: sign every sample transaction, dispatch them concurrently and
+    // report the aggregate throughput instead of the one-at-a-time loop.
+    if args.batch {
+        let batches: Vec<Vec<Instruction>> = (0..args.num_transactions)
+            .map(|_| {
+                let recipient = Keypair::new().pubkey();
+                vec![system_instruction::transfer(&payer.pubkey(), &recipient, 100)]
+            })
+            .collect();
+
+        info!("Submitting {} transactions as a batch", batches.len());
+        let outcome = transaction_service
+            .submit_transactions(&payer, batches)
+            .await?;
+
+        let succeeded = outcome.results.iter().filter(|r| r.is_ok()).count();
+        counter!("solana.transactions.total", args.num_transactions as u64);
+        counter!("solana.transactions.success", succeeded as u64);
+        counter!(
+            "solana.transactions.failed",
+            (outcome.results.len() - succeeded) as u64
+        );
+        info!(
+            "Batch completed: {}/{} confirmed ({:.1} TPS)",
+            succeeded,
+            outcome.results.len(),
+            outcome.tps
+        );
+
+        // Let metrics be scraped
+        sleep(Duration::from_secs(5)).await;
+        return Ok(());
+    }
+
     // Run sample transactions
     for i in 0..args.num_transactions {
         info!("Sending transaction {}/{}", i + 1, args.num_transactions);
@@ -121,9 +207,15 @@ async fn main() -> Result<()> {
 
         // Submit the transaction
         let start = Instant::now();
-        let result = transaction_service
-            .submit_transaction(&payer, vec![instruction])
-            .await;
+        let result = if args.use_tpu {
+            transaction_service
+                .submit_transaction_via_tpu(&payer, vec![instruction])
+                .await
+        } else {
+            transaction_service
+                .submit_transaction(&payer, vec![instruction])
+                .await
+        };
 
         // Update metrics
         counter!("solana.transactions.total", 1);
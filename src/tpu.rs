@@ -0,0 +1,190 @@
+use crate::error::TransactionError;
+use log::{debug, warn};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    net::{SocketAddr, UdpSocket},
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::time::sleep;
+
+/// How often the background task refreshes the leader map.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Forwards serialized transactions directly to the current and upcoming slot
+/// leaders, bypassing `RpcClient::send_transaction`.
+///
+/// A background task keeps a `Pubkey -> TPU SocketAddr` map fresh from
+/// `get_cluster_nodes`, while the leader schedule is consulted on each send to
+/// work out which validators own the next few slots.
+pub struct TpuSender {
+    client: Arc<RpcClient>,
+    /// Validator identity -> TPU address, refreshed in the background.
+    tpu_peers: Arc<RwLock<HashMap<Pubkey, SocketAddr>>>,
+    socket: UdpSocket,
+    /// Number of upcoming slots whose leaders each transaction is fanned out
+    /// to. A leader holding several of those slots is sent to only once.
+    fanout: u64,
+}
+
+impl TpuSender {
+    /// Build a `TpuSender` and spawn the background refresh task.
+    pub fn start(client: Arc<RpcClient>, fanout: u64) -> Result<Self, TransactionError> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .map_err(|err| TransactionError::Other(format!("failed to bind UDP socket: {}", err)))?;
+
+        let tpu_peers = Arc::new(RwLock::new(HashMap::new()));
+
+        let sender = Self {
+            client: client.clone(),
+            tpu_peers: tpu_peers.clone(),
+            socket,
+            fanout,
+        };
+
+        // Populate once synchronously so the first send has something to target,
+        // then keep refreshing in the background. The refresh hits a blocking
+        // RPC (`get_cluster_nodes`), so it is driven on a blocking thread rather
+        // than directly on a tokio worker.
+        sender.refresh_peers();
+        tokio::spawn(async move {
+            loop {
+                sleep(REFRESH_INTERVAL).await;
+                let client = client.clone();
+                let tpu_peers = tpu_peers.clone();
+                if let Err(err) =
+                    tokio::task::spawn_blocking(move || Self::refresh_peers_into(&client, &tpu_peers))
+                        .await
+                {
+                    warn!("TPU peer refresh task failed to join: {}", err);
+                }
+            }
+        });
+
+        Ok(sender)
+    }
+
+    /// Fan a serialized transaction out to the next `fanout` slot leaders.
+    ///
+    /// Returns the number of leaders the packet was dispatched to. Confirmation
+    /// is handled by the caller, which also rebroadcasts over this same path so
+    /// a landing is attributable to the direct forward rather than to RPC.
+    ///
+    /// This resolves the leader schedule via blocking RPCs and then sends
+    /// synchronously over the socket, so it must be driven on a blocking thread.
+    ///
+    /// NOTE: packets are sent as plain UDP datagrams to each leader's advertised
+    /// `tpu` port. Current validators serve TPU over QUIC and drop unsolicited
+    /// UDP, so on today's clusters this forward will usually not land on its own.
+    /// Landing transactions directly requires a QUIC client (connection setup,
+    /// stream framing and stake-weighted throttling); until that is wired up the
+    /// measured "direct TPU landing rate" should be read as a lower bound. The
+    /// caller only falls back to `RpcClient::send_transaction` when no leader
+    /// addresses are known, so a benchmark in TPU mode never silently credits
+    /// RPC-delivered transactions to this path.
+    pub fn send_wire_transaction(&self, wire_transaction: &[u8]) -> Result<usize, TransactionError> {
+        let leaders = self.upcoming_leader_tpus()?;
+        if leaders.is_empty() {
+            return Err(TransactionError::SendError(
+                "no upcoming leader TPU addresses known".to_string(),
+            ));
+        }
+
+        let mut dispatched = 0;
+        for addr in &leaders {
+            match self.socket.send_to(wire_transaction, addr) {
+                Ok(_) => dispatched += 1,
+                Err(err) => warn!("Failed to forward transaction to leader {}: {}", addr, err),
+            }
+        }
+
+        debug!("Forwarded transaction to {}/{} leaders", dispatched, leaders.len());
+        Ok(dispatched)
+    }
+
+    /// Resolve the TPU addresses of the leaders for the next `fanout` slots.
+    fn upcoming_leader_tpus(&self) -> Result<Vec<SocketAddr>, TransactionError> {
+        let slot = self
+            .client
+            .get_slot()
+            .map_err(|err| TransactionError::RpcError(err.to_string()))?;
+        let epoch_info = self
+            .client
+            .get_epoch_info()
+            .map_err(|err| TransactionError::RpcError(err.to_string()))?;
+
+        // First slot of the current epoch, used to turn absolute slots into the
+        // epoch-relative indices of the leader schedule.
+        let epoch_start = slot.saturating_sub(epoch_info.slot_index);
+        let schedule = self
+            .client
+            .get_leader_schedule(Some(slot))
+            .map_err(|err| TransactionError::RpcError(err.to_string()))?
+            .ok_or_else(|| TransactionError::RpcError("no leader schedule for slot".to_string()))?;
+
+        let peers = self
+            .tpu_peers
+            .read()
+            .expect("tpu peers lock poisoned")
+            .clone();
+
+        // The schedule maps an identity to the epoch-relative slot indices it
+        // leads; invert it so we can ask "who leads slot X".
+        let mut leader_for_index: HashMap<u64, Pubkey> = HashMap::new();
+        for (identity, indices) in &schedule {
+            let Ok(pubkey) = Pubkey::from_str(identity) else {
+                continue;
+            };
+            for index in indices {
+                leader_for_index.insert(*index as u64, pubkey);
+            }
+        }
+
+        let mut addrs = Vec::new();
+        for offset in 0..self.fanout {
+            let index = slot.saturating_add(offset).saturating_sub(epoch_start);
+            if let Some(leader) = leader_for_index.get(&index) {
+                if let Some(addr) = peers.get(leader) {
+                    if !addrs.contains(addr) {
+                        addrs.push(*addr);
+                    }
+                }
+            }
+        }
+
+        Ok(addrs)
+    }
+
+    fn refresh_peers(&self) {
+        Self::refresh_peers_into(&self.client, &self.tpu_peers);
+    }
+
+    fn refresh_peers_into(
+        client: &Arc<RpcClient>,
+        tpu_peers: &Arc<RwLock<HashMap<Pubkey, SocketAddr>>>,
+    ) {
+        let nodes = match client.get_cluster_nodes() {
+            Ok(nodes) => nodes,
+            Err(err) => {
+                warn!("Failed to refresh cluster nodes: {}", err);
+                return;
+            }
+        };
+
+        let mut map = HashMap::new();
+        for node in nodes {
+            let Ok(pubkey) = Pubkey::from_str(&node.pubkey) else {
+                continue;
+            };
+            if let Some(Ok(addr)) = node.tpu.map(|tpu| tpu.parse::<SocketAddr>()) {
+                map.insert(pubkey, addr);
+            }
+        }
+
+        debug!("Refreshed TPU peer map with {} leaders", map.len());
+        *tpu_peers.write().expect("tpu peers lock poisoned") = map;
+    }
+}
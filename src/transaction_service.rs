@@ -1,32 +1,180 @@
 use crate::error::TransactionError;
+use crate::tpu::TpuSender;
 use log::{debug, info, warn};
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    hash::Hash,
     instruction::Instruction,
     message::Message,
+    nonce::state::{State as NonceState, Versions},
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
+    system_instruction,
     transaction::Transaction,
 };
-use std::{sync::Arc, time::Duration};
+use metrics::gauge;
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::sync::Semaphore;
 use tokio::time::sleep;
 
+/// Upper bound on transactions signed and dispatched concurrently by
+/// [`TransactionService::submit_transactions`].
+const MAX_CONCURRENT_SENDS: usize = 32;
+
+/// Interval between signature-status polls while awaiting confirmation.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Rebroadcast the transaction once every this many confirmation polls.
+const REBROADCAST_INTERVAL: u64 = 3;
+
+/// Terminal poll budget for the confirmation loop, so a transaction that never
+/// lands (or a persistently failing block-height query) cannot poll forever.
+/// At [`CONFIRMATION_POLL_INTERVAL`] this is roughly a minute of polling.
+const MAX_CONFIRMATION_POLLS: u64 = 150;
+
+/// Aggregate result of a batch submission.
+pub struct BatchOutcome {
+    /// Per-transaction results, in the same order as the submitted batches.
+    pub results: Vec<Result<Signature, TransactionError>>,
+    /// Confirmed transactions divided by wall-clock seconds elapsed.
+    pub tps: f64,
+}
+
+/// The minimal slice of the RPC client the service depends on.
+///
+/// Hiding these calls behind a trait keeps the retry/backoff loop
+/// testable: a mock implementation can return scripted blockhashes, send
+/// errors and confirmation outcomes so `is_retriable_error` and the retry
+/// logic are exercised without live network I/O.
+pub trait TransactionSender: Send + Sync {
+    fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, TransactionError>;
+
+    fn get_latest_blockhash(&self) -> Result<Hash, TransactionError>;
+
+    fn send_transaction(
+        &self,
+        tx: &Transaction,
+        config: &RpcSendTransactionConfig,
+    ) -> Result<Signature, TransactionError>;
+
+    fn confirm_transaction_with_commitment(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, TransactionError>;
+
+    /// Read the blockhash currently stored in a durable nonce account.
+    fn get_nonce_blockhash(&self, nonce_account: &Pubkey) -> Result<Hash, TransactionError>;
+
+    /// Fetch a recent blockhash together with the last block height at which it
+    /// remains valid.
+    fn get_latest_blockhash_with_valid_height(&self) -> Result<(Hash, u64), TransactionError>;
+
+    /// Current confirmed block height of the cluster.
+    fn get_block_height(&self) -> Result<u64, TransactionError>;
+}
+
+/// Adapts the concrete Solana `RpcClient` to [`TransactionSender`], mapping its
+/// errors onto the crate's [`TransactionError`] variants.
+impl TransactionSender for RpcClient {
+    fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, TransactionError> {
+        RpcClient::get_balance(self, pubkey)
+            .map_err(|err| TransactionError::RpcError(err.to_string()))
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash, TransactionError> {
+        RpcClient::get_latest_blockhash(self)
+            .map_err(|err| TransactionError::RpcError(err.to_string()))
+    }
+
+    fn send_transaction(
+        &self,
+        tx: &Transaction,
+        config: &RpcSendTransactionConfig,
+    ) -> Result<Signature, TransactionError> {
+        RpcClient::send_transaction_with_config(self, tx, config.clone())
+            .map_err(|err| TransactionError::SendError(err.to_string()))
+    }
+
+    fn confirm_transaction_with_commitment(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, TransactionError> {
+        RpcClient::confirm_transaction_with_commitment(self, signature, commitment)
+            .map(|response| response.value)
+            .map_err(|err| TransactionError::ConfirmationError(err.to_string()))
+    }
+
+    fn get_nonce_blockhash(&self, nonce_account: &Pubkey) -> Result<Hash, TransactionError> {
+        let account = RpcClient::get_account(self, nonce_account)
+            .map_err(|err| TransactionError::RpcError(err.to_string()))?;
+        let versions: Versions = bincode::deserialize(&account.data)
+            .map_err(|err| TransactionError::RpcError(err.to_string()))?;
+        match versions.state() {
+            NonceState::Initialized(data) => Ok(data.blockhash()),
+            NonceState::Uninitialized => Err(TransactionError::RpcError(
+                "nonce account is not initialized".to_string(),
+            )),
+        }
+    }
+
+    fn get_latest_blockhash_with_valid_height(&self) -> Result<(Hash, u64), TransactionError> {
+        RpcClient::get_latest_blockhash_with_commitment(self, self.commitment())
+            .map_err(|err| TransactionError::RpcError(err.to_string()))
+    }
+
+    fn get_block_height(&self) -> Result<u64, TransactionError> {
+        RpcClient::get_block_height(self).map_err(|err| TransactionError::RpcError(err.to_string()))
+    }
+}
+
 /// Service for managing Solana transactions
 pub struct TransactionService {
-    client: Arc<RpcClient>,
+    client: Arc<dyn TransactionSender>,
     max_retries: u32,
+    send_config: RpcSendTransactionConfig,
+    tpu: Option<Arc<TpuSender>>,
 }
 
 impl TransactionService {
     /// Create a new TransactionService
-    pub fn new(client: Arc<RpcClient>, max_retries: u32) -> Self {
+    pub fn new(client: Arc<dyn TransactionSender>, max_retries: u32) -> Self {
         Self {
             client,
             max_retries,
+            send_config: RpcSendTransactionConfig::default(),
+            tpu: None,
         }
     }
 
+    /// Override the [`RpcSendTransactionConfig`] used for every send.
+    ///
+    /// This controls preflight simulation (`skip_preflight`), the commitment
+    /// preflight is checked against, the number of server-side rebroadcast
+    /// retries and the minimum context slot the RPC node must have reached.
+    pub fn set_send_config(&mut self, send_config: RpcSendTransactionConfig) {
+        self.send_config = send_config;
+    }
+
+    /// Enable the direct TPU send path, fanning transactions out to the next
+    /// `fanout` slot leaders. The supplied RPC client drives leader discovery;
+    /// the confirmation/fallback path continues to use the configured sender.
+    pub fn enable_tpu(
+        &mut self,
+        rpc: Arc<RpcClient>,
+        fanout: u64,
+    ) -> Result<(), TransactionError> {
+        self.tpu = Some(Arc::new(TpuSender::start(rpc, fanout)?));
+        Ok(())
+    }
+
     /// Get the balance of a Solana account
     pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, TransactionError> {
         match self.client.get_balance(pubkey) {
@@ -64,15 +212,16 @@ impl TransactionService {
                 sleep(backoff).await;
             }
 
-            // Get a recent blockhash
-            let recent_blockhash = match self.client.get_latest_blockhash() {
-                Ok(blockhash) => blockhash,
-                Err(err) => {
-                    warn!("Failed to get recent blockhash: {}", err);
-                    sleep(blockhash_query_interval).await;
-                    continue;
-                }
-            };
+            // Get a recent blockhash along with the height at which it expires.
+            let (recent_blockhash, last_valid_block_height) =
+                match self.client.get_latest_blockhash_with_valid_height() {
+                    Ok(blockhash) => blockhash,
+                    Err(err) => {
+                        warn!("Failed to get recent blockhash: {}", err);
+                        sleep(blockhash_query_interval).await;
+                        continue;
+                    }
+                };
 
             // Create the transaction
             let message = Message::new(&instructions, Some(&payer.pubkey()));
@@ -80,7 +229,7 @@ impl TransactionService {
             tx.sign(&[payer], recent_blockhash);
 
             // Send the transaction
-            match self.send_and_confirm_transaction(&tx).await {
+            match self.send_and_confirm(tx, last_valid_block_height).await {
                 Ok(signature) => return Ok(signature),
                 Err(err) => {
                     warn!("Transaction failed: {}", err);
@@ -94,32 +243,386 @@ impl TransactionService {
         }
     }
 
-    /// Send and confirm a transaction
-    async fn send_and_confirm_transaction(
+    /// Sign many transactions and dispatch them concurrently, sharing a single
+    /// cached blockhash across the batch.
+    ///
+    /// Transactions are signed up front and then sent under a bounded
+    /// concurrency pool so a large batch does not overwhelm the RPC node. Each
+    /// transaction is retried on transient errors up to `max_retries` times,
+    /// matching the single-transaction path (the shared blockhash is reused
+    /// rather than re-signed — see [`Self::send_and_confirm_with_retries`]). The
+    /// returned [`BatchOutcome`] carries per-transaction results alongside the
+    /// sustained throughput, which is also published as the
+    /// `solana.transactions.tps` gauge.
+    pub async fn submit_transactions(
         &self,
-        tx: &Transaction,
+        payer: &Keypair,
+        batches: Vec<Vec<Instruction>>,
+    ) -> Result<BatchOutcome, TransactionError> {
+        // One blockhash is shared by the whole batch to avoid hammering the RPC
+        // node with a query per transaction.
+        let (recent_blockhash, last_valid_block_height) =
+            self.client.get_latest_blockhash_with_valid_height()?;
+
+        // Signing stage: build and sign every transaction, one after another on
+        // this task, before any of them are dispatched. Signing is not
+        // parallelised; only the sends below run concurrently.
+        let signed: Vec<Transaction> = batches
+            .into_iter()
+            .map(|instructions| {
+                let message = Message::new(&instructions, Some(&payer.pubkey()));
+                let mut tx = Transaction::new_unsigned(message);
+                tx.sign(&[payer], recent_blockhash);
+                tx
+            })
+            .collect();
+
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_SENDS));
+        let start = Instant::now();
+
+        // The RPC send/confirm is synchronous, so each transaction is driven on
+        // a blocking thread rather than a tokio worker; the permit bounds how
+        // many run at once.
+        let max_retries = self.max_retries;
+        let mut handles = Vec::with_capacity(signed.len());
+        for tx in signed {
+            let client = self.client.clone();
+            let send_config = self.send_config.clone();
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("semaphore closed unexpectedly");
+            handles.push(tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                Self::send_and_confirm_with_retries(
+                    &client,
+                    &tx,
+                    last_valid_block_height,
+                    &send_config,
+                    max_retries,
+                )
+            }));
+        }
+
+        let mut results = Vec::with_capacity(handles.len());
+        let mut confirmed = 0u64;
+        for handle in handles {
+            match handle.await {
+                Ok(Ok(signature)) => {
+                    confirmed += 1;
+                    results.push(Ok(signature));
+                }
+                Ok(Err(err)) => results.push(Err(err)),
+                Err(join_err) => results.push(Err(TransactionError::Other(join_err.to_string()))),
+            }
+        }
+
+        let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+        let tps = confirmed as f64 / elapsed;
+        gauge!("solana.transactions.tps", tps);
+        info!(
+            "Batch submitted: {}/{} confirmed in {:.2}s ({:.1} TPS)",
+            confirmed,
+            results.len(),
+            elapsed,
+            tps
+        );
+
+        Ok(BatchOutcome { results, tps })
+    }
+
+    /// Submit a transaction over the direct TPU path with retry logic.
+    ///
+    /// The signed transaction is forwarded straight to the upcoming slot
+    /// leaders; the RPC client is then used to confirm it and, if no leader
+    /// addresses are available, to fall back to `send_transaction`.
+    pub async fn submit_transaction_via_tpu(
+        &self,
+        payer: &Keypair,
+        instructions: Vec<Instruction>,
+    ) -> Result<Signature, TransactionError> {
+        let tpu = self.tpu.as_ref().ok_or_else(|| {
+            TransactionError::Other("TPU send path is not enabled".to_string())
+        })?;
+
+        let mut attempt = 0;
+        let blockhash_query_interval = Duration::from_millis(1000);
+
+        loop {
+            attempt += 1;
+
+            if attempt > self.max_retries + 1 {
+                return Err(TransactionError::MaxRetriesExceeded);
+            }
+
+            if attempt > 1 {
+                debug!(
+                    "Retrying TPU transaction (attempt {}/{})",
+                    attempt - 1,
+                    self.max_retries
+                );
+                let backoff = Duration::from_millis(500 * 2u64.pow(attempt - 2));
+                sleep(backoff).await;
+            }
+
+            let (recent_blockhash, last_valid_block_height) =
+                match self.client.get_latest_blockhash_with_valid_height() {
+                    Ok(blockhash) => blockhash,
+                    Err(err) => {
+                        warn!("Failed to get recent blockhash: {}", err);
+                        sleep(blockhash_query_interval).await;
+                        continue;
+                    }
+                };
+
+            let message = Message::new(&instructions, Some(&payer.pubkey()));
+            let mut tx = Transaction::new_unsigned(message);
+            tx.sign(&[payer], recent_blockhash);
+
+            let signature = tx.signatures[0];
+            let wire_transaction = match bincode::serialize(&tx) {
+                Ok(bytes) => bytes,
+                Err(err) => return Err(TransactionError::SendError(err.to_string())),
+            };
+
+            // Forward directly to the leaders on a blocking thread (the leader
+            // schedule lookup and UDP sends are synchronous); fall back to the
+            // RPC node when no leader addresses are currently known.
+            let forward = {
+                let tpu = tpu.clone();
+                let wire_transaction = wire_transaction.clone();
+                tokio::task::spawn_blocking(move || tpu.send_wire_transaction(&wire_transaction))
+                    .await
+                    .map_err(|err| TransactionError::Other(err.to_string()))?
+            };
+            match forward {
+                Ok(_) => {}
+                Err(err) => {
+                    warn!("TPU forward failed, falling back to RPC: {}", err);
+                    if let Err(send_err) = self.client.send_transaction(&tx, &self.send_config) {
+                        if !Self::is_retriable_error(&send_err) {
+                            return Err(send_err);
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // Confirm via the shared poll/rebroadcast loop so a forwarded tx is
+            // given time to land (and is re-forwarded over TPU) before it is
+            // declared failed.
+            match self
+                .confirm_forwarded(
+                    tpu.clone(),
+                    wire_transaction,
+                    signature,
+                    last_valid_block_height,
+                )
+                .await
+            {
+                Ok(signature) => return Ok(signature),
+                Err(err) => {
+                    warn!("TPU transaction not confirmed: {}", err);
+                    if !Self::is_retriable_error(&err) {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Submit a transaction backed by a durable nonce account.
+    ///
+    /// The nonce account's stored blockhash is used in place of a recent
+    /// blockhash, and an `advance_nonce_account` instruction is prepended so
+    /// the nonce rolls forward on execution. Because durable-nonce
+    /// transactions never expire from blockhash staleness, a "blockhash not
+    /// found" failure is handled by re-reading the nonce value rather than
+    /// fetching a new recent blockhash.
+    pub async fn submit_transaction_with_nonce(
+        &self,
+        payer: &Keypair,
+        nonce_account: &Pubkey,
+        nonce_authority: &Keypair,
+        instructions: Vec<Instruction>,
+    ) -> Result<Signature, TransactionError> {
+        let mut attempt = 0;
+        let nonce_query_interval = Duration::from_millis(1000);
+
+        loop {
+            attempt += 1;
+
+            if attempt > self.max_retries + 1 {
+                return Err(TransactionError::MaxRetriesExceeded);
+            }
+
+            if attempt > 1 {
+                debug!(
+                    "Retrying durable-nonce transaction (attempt {}/{})",
+                    attempt - 1,
+                    self.max_retries
+                );
+                let backoff = Duration::from_millis(500 * 2u64.pow(attempt - 2));
+                sleep(backoff).await;
+            }
+
+            // Read the nonce value fresh on every attempt: for durable nonces
+            // the stored blockhash is the source of truth, so a stale-blockhash
+            // failure is cured by re-fetching the nonce rather than a recent
+            // blockhash.
+            let nonce_hash = match self.client.get_nonce_blockhash(nonce_account) {
+                Ok(hash) => hash,
+                Err(err) => {
+                    warn!("Failed to read nonce account: {}", err);
+                    sleep(nonce_query_interval).await;
+                    continue;
+                }
+            };
+
+            // The advance instruction must come first in the transaction.
+            let mut nonced_instructions = Vec::with_capacity(instructions.len() + 1);
+            nonced_instructions.push(system_instruction::advance_nonce_account(
+                nonce_account,
+                &nonce_authority.pubkey(),
+            ));
+            nonced_instructions.extend(instructions.iter().cloned());
+
+            let message = Message::new(&nonced_instructions, Some(&payer.pubkey()));
+            let mut tx = Transaction::new_unsigned(message);
+            tx.sign(&[payer, nonce_authority], nonce_hash);
+
+            // Durable-nonce transactions never expire from blockhash staleness,
+            // so there is no validity window to bound the wait — pass
+            // `u64::MAX` and let the confirmation loop's poll budget be the only
+            // terminal condition before a fresh nonce read.
+            match self.send_and_confirm(tx, u64::MAX).await {
+                Ok(signature) => return Ok(signature),
+                Err(err) => {
+                    warn!("Durable-nonce transaction failed: {}", err);
+                    if !Self::is_retriable_error(&err) {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Poll for confirmation of an already-dispatched transaction, periodically
+    /// rebroadcasting the serialized transaction while its blockhash can still
+    /// land.
+    ///
+    /// This is the single confirmation strategy shared by every send path. The
+    /// transaction is re-sent every few polls to survive dropped packets;
+    /// success is returned on a confirmed status. Once the cluster's block
+    /// height passes `last_valid_block_height` the blockhash can never land, so
+    /// a [`TransactionError::ConfirmationError`] is returned to trigger a fresh
+    /// blockhash and a new attempt. The loop is also bounded by
+    /// [`MAX_CONFIRMATION_POLLS`] so it can never poll forever.
+    ///
+    /// The RPC client is synchronous, so this blocks and is meant to be driven
+    /// from a blocking thread — see [`TransactionService::send_and_confirm`].
+    ///
+    /// `rebroadcast` is invoked every [`REBROADCAST_INTERVAL`] polls so each send
+    /// path can re-send over its own channel (RPC for the default paths, the TPU
+    /// socket for the forwarded path) while sharing this single loop.
+    fn confirm_with_rebroadcast(
+        client: &Arc<dyn TransactionSender>,
+        signature: Signature,
+        last_valid_block_height: u64,
+        mut rebroadcast: impl FnMut(),
     ) -> Result<Signature, TransactionError> {
-        let signature = match self.client.send_transaction(tx) {
-            Ok(sig) => sig,
-            Err(err) => return Err(TransactionError::SendError(err.to_string())),
-        };
-
-        // Wait for confirmation
-        match self
-            .client
-            .confirm_transaction_with_commitment(&signature, CommitmentConfig::confirmed())
-        {
-            Ok(result) => {
-                if !result.value {
+        let mut polls: u64 = 0;
+        loop {
+            match client
+                .confirm_transaction_with_commitment(&signature, CommitmentConfig::confirmed())
+            {
+                Ok(true) => return Ok(signature),
+                Ok(false) => {}
+                Err(err) => debug!("Signature status poll failed: {}", err),
+            }
+
+            match client.get_block_height() {
+                Ok(height) if height > last_valid_block_height => {
                     return Err(TransactionError::ConfirmationError(
-                        "Transaction was not confirmed".to_string(),
+                        "blockhash expired before confirmation".to_string(),
                     ));
                 }
+                Ok(_) => {}
+                Err(err) => debug!("Block height poll failed: {}", err),
             }
-            Err(err) => return Err(TransactionError::ConfirmationError(err.to_string())),
+
+            polls += 1;
+            if polls >= MAX_CONFIRMATION_POLLS {
+                return Err(TransactionError::ConfirmationError(
+                    "confirmation timeout".to_string(),
+                ));
+            }
+
+            // Rebroadcast periodically to ride out dropped packets.
+            if polls % REBROADCAST_INTERVAL == 0 {
+                rebroadcast();
+            }
+
+            thread::sleep(CONFIRMATION_POLL_INTERVAL);
         }
+    }
 
-        Ok(signature)
+    /// Blocking send-then-confirm using the shared [`Self::confirm_with_rebroadcast`]
+    /// loop. Shared by the single-transaction, batch and nonce paths.
+    fn send_and_confirm_with(
+        client: &Arc<dyn TransactionSender>,
+        tx: &Transaction,
+        last_valid_block_height: u64,
+        send_config: &RpcSendTransactionConfig,
+    ) -> Result<Signature, TransactionError> {
+        let signature = client.send_transaction(tx, send_config)?;
+        Self::confirm_with_rebroadcast(client, signature, last_valid_block_height, || {
+            if let Err(err) = client.send_transaction(tx, send_config) {
+                debug!("Rebroadcast failed: {}", err);
+            }
+        })
+    }
+
+    /// Send a transaction and await confirmation on a blocking thread so the
+    /// synchronous RPC polling never occupies a tokio worker.
+    async fn send_and_confirm(
+        &self,
+        tx: Transaction,
+        last_valid_block_height: u64,
+    ) -> Result<Signature, TransactionError> {
+        let client = self.client.clone();
+        let send_config = self.send_config.clone();
+        tokio::task::spawn_blocking(move || {
+            Self::send_and_confirm_with(&client, &tx, last_valid_block_height, &send_config)
+        })
+        .await
+        .map_err(|err| TransactionError::Other(err.to_string()))?
+    }
+
+    /// Await confirmation of a transaction that was forwarded over TPU, on a
+    /// blocking thread.
+    ///
+    /// Rebroadcasts re-forward the serialized transaction over the TPU socket
+    /// (not the RPC node), so a confirmed landing in TPU mode reflects direct
+    /// leader delivery rather than the RPC fallback.
+    async fn confirm_forwarded(
+        &self,
+        tpu: Arc<TpuSender>,
+        wire_transaction: Vec<u8>,
+        signature: Signature,
+        last_valid_block_height: u64,
+    ) -> Result<Signature, TransactionError> {
+        let client = self.client.clone();
+        tokio::task::spawn_blocking(move || {
+            Self::confirm_with_rebroadcast(&client, signature, last_valid_block_height, || {
+                if let Err(err) = tpu.send_wire_transaction(&wire_transaction) {
+                    debug!("TPU rebroadcast failed: {}", err);
+                }
+            })
+        })
+        .await
+        .map_err(|err| TransactionError::Other(err.to_string()))?
     }
 
     /// Determine if an error is retriable
@@ -135,9 +638,234 @@ impl TransactionService {
                     || msg.contains("too many requests")
             }
             TransactionError::ConfirmationError(msg) => {
-                msg.contains("timeout") || msg.contains("connection closed")
+                msg.contains("timeout")
+                    || msg.contains("connection closed")
+                    || msg.contains("blockhash expired")
             }
             _ => false,
         }
     }
+
+    /// Send and confirm one already-signed transaction, retrying on transient
+    /// errors up to `max_retries` times.
+    ///
+    /// The batch path shares one blockhash across every transaction, so unlike
+    /// the single-transaction path this cannot re-sign on retry; it resubmits
+    /// the same signed transaction. That still rides out transient send/confirm
+    /// failures (dropped packets, rate limits), while a genuinely expired
+    /// blockhash simply exhausts the retries and surfaces the last error.
+    fn send_and_confirm_with_retries(
+        client: &Arc<dyn TransactionSender>,
+        tx: &Transaction,
+        last_valid_block_height: u64,
+        send_config: &RpcSendTransactionConfig,
+        max_retries: u32,
+    ) -> Result<Signature, TransactionError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match Self::send_and_confirm_with(client, tx, last_valid_block_height, send_config) {
+                Ok(signature) => return Ok(signature),
+                Err(err) => {
+                    if attempt > max_retries || !Self::is_retriable_error(&err) {
+                        return Err(err);
+                    }
+                    debug!(
+                        "Retrying batch transaction (attempt {}/{}): {}",
+                        attempt, max_retries, err
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::system_instruction;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Scriptable [`TransactionSender`] used to drive the retry loop without any
+    /// network I/O. Each queue is consumed front-to-back; once empty the mock
+    /// falls back to a successful response.
+    struct MockSender {
+        blockhash: Hash,
+        send_results: Mutex<VecDeque<Result<Signature, TransactionError>>>,
+        confirm_results: Mutex<VecDeque<Result<bool, TransactionError>>>,
+    }
+
+    impl MockSender {
+        fn new() -> Self {
+            Self {
+                blockhash: Hash::new_unique(),
+                send_results: Mutex::new(VecDeque::new()),
+                confirm_results: Mutex::new(VecDeque::new()),
+            }
+        }
+
+        fn queue_send(&self, result: Result<Signature, TransactionError>) {
+            self.send_results.lock().unwrap().push_back(result);
+        }
+    }
+
+    impl TransactionSender for MockSender {
+        fn get_balance(&self, _pubkey: &Pubkey) -> Result<u64, TransactionError> {
+            Ok(1_000_000_000)
+        }
+
+        fn get_latest_blockhash(&self) -> Result<Hash, TransactionError> {
+            Ok(self.blockhash)
+        }
+
+        fn send_transaction(
+            &self,
+            _tx: &Transaction,
+            _config: &RpcSendTransactionConfig,
+        ) -> Result<Signature, TransactionError> {
+            self.send_results
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| Ok(Signature::default()))
+        }
+
+        fn confirm_transaction_with_commitment(
+            &self,
+            _signature: &Signature,
+            _commitment: CommitmentConfig,
+        ) -> Result<bool, TransactionError> {
+            self.confirm_results
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or(Ok(true))
+        }
+
+        fn get_nonce_blockhash(&self, _nonce_account: &Pubkey) -> Result<Hash, TransactionError> {
+            Ok(self.blockhash)
+        }
+
+        fn get_latest_blockhash_with_valid_height(
+            &self,
+        ) -> Result<(Hash, u64), TransactionError> {
+            Ok((self.blockhash, u64::MAX))
+        }
+
+        fn get_block_height(&self) -> Result<u64, TransactionError> {
+            Ok(0)
+        }
+    }
+
+    fn service_with(mock: MockSender, max_retries: u32) -> TransactionService {
+        TransactionService::new(Arc::new(mock), max_retries)
+    }
+
+    fn sample_instructions(payer: &Keypair) -> Vec<Instruction> {
+        vec![system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            1,
+        )]
+    }
+
+    #[test]
+    fn classifies_retriable_errors() {
+        assert!(TransactionService::is_retriable_error(
+            &TransactionError::RpcError("node down".to_string())
+        ));
+        assert!(TransactionService::is_retriable_error(
+            &TransactionError::SendError("blockhash not found".to_string())
+        ));
+        assert!(!TransactionService::is_retriable_error(
+            &TransactionError::SendError("account not found".to_string())
+        ));
+        assert!(!TransactionService::is_retriable_error(
+            &TransactionError::InsufficientFunds
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_then_succeeds() {
+        let mock = MockSender::new();
+        // First send fails with a retriable error, the retry then succeeds.
+        mock.queue_send(Err(TransactionError::SendError(
+            "blockhash not found".to_string(),
+        )));
+        let payer = Keypair::new();
+        let service = service_with(mock, 3);
+
+        let result = service
+            .submit_transaction(&payer, sample_instructions(&payer))
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_max_retries() {
+        let mock = MockSender::new();
+        for _ in 0..8 {
+            mock.queue_send(Err(TransactionError::SendError("timeout".to_string())));
+        }
+        let payer = Keypair::new();
+        let service = service_with(mock, 2);
+
+        let result = service
+            .submit_transaction(&payer, sample_instructions(&payer))
+            .await;
+        assert!(matches!(result, Err(TransactionError::MaxRetriesExceeded)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_retriable_error_fails_immediately() {
+        let mock = MockSender::new();
+        mock.queue_send(Err(TransactionError::SendError(
+            "account not found".to_string(),
+        )));
+        let payer = Keypair::new();
+        let service = service_with(mock, 5);
+
+        let result = service
+            .submit_transaction(&payer, sample_instructions(&payer))
+            .await;
+        assert!(matches!(result, Err(TransactionError::SendError(_))));
+    }
+
+    #[tokio::test]
+    async fn batch_submission_confirms_and_reports_tps() {
+        let mock = MockSender::new();
+        let payer = Keypair::new();
+        let service = service_with(mock, 3);
+
+        let batches = vec![
+            sample_instructions(&payer),
+            sample_instructions(&payer),
+            sample_instructions(&payer),
+        ];
+        let outcome = service.submit_transactions(&payer, batches).await.unwrap();
+
+        assert_eq!(outcome.results.len(), 3);
+        assert!(outcome.results.iter().all(|r| r.is_ok()));
+        assert!(outcome.tps.is_finite() && outcome.tps >= 0.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn durable_nonce_submission_succeeds() {
+        let mock = MockSender::new();
+        let payer = Keypair::new();
+        let nonce_authority = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let service = service_with(mock, 3);
+
+        let result = service
+            .submit_transaction_with_nonce(
+                &payer,
+                &nonce_account,
+                &nonce_authority,
+                sample_instructions(&payer),
+            )
+            .await;
+        assert!(result.is_ok());
+    }
 }